@@ -0,0 +1,162 @@
+/*! # Fetching dictionaries on demand
+
+Bundling every dictionary, or enabling `embed_all`, bloats the compiled
+artifact with languages a given program may never use. This module offers
+the opposite trade-off: [`Load::fetch`](crate::Load::fetch) resolves a
+dictionary's download URL from a [`DictionarySource`], downloads it into
+a cache directory the first time it is needed, and reuses the cached
+copy on every subsequent call.
+
+```norun
+use hyphenation::{Load, Standard, Language};
+
+let english_us = Standard::fetch(Language::EnglishUS) ?;
+```
+
+This is gated behind the `fetch` feature.
+
+The cache directory defaults to the platform's data directory, and can be
+overridden with the `HYPHENATION_CACHE_DIR` environment variable. The
+download location defaults to a GitHub Releases template keyed on the
+language code and dictionary kind, and can be overridden with
+[`DictionarySource::with_base_url`].
+*/
+
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use hyphenation_commons::Language;
+
+use crate::load::{Error, Result};
+
+
+const DEFAULT_BASE_URL : &str =
+    "https://github.com/rust-spandex/hyphenation/releases/latest/download";
+
+/// The location dictionaries are downloaded from and cached to.
+pub struct DictionarySource {
+    base_url : String,
+    cache_dir : PathBuf,
+}
+
+impl DictionarySource {
+    /// The default source: dictionaries are downloaded from the crate's
+    /// release assets and cached under the platform data directory, or
+    /// `$HYPHENATION_CACHE_DIR` if set.
+    pub fn default_source() -> Self {
+        DictionarySource {
+            base_url : DEFAULT_BASE_URL.to_string(),
+            cache_dir : default_cache_dir(),
+        }
+    }
+
+    /// Use `base_url` as the template the download URL is built from,
+    /// instead of the crate's own release assets.
+    pub fn with_base_url<S : Into<String>>(mut self, base_url : S) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Cache downloaded dictionaries under `dir` instead of the default
+    /// location.
+    pub fn with_cache_dir<P : Into<PathBuf>>(mut self, dir : P) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    fn url_for(&self, lang : Language, suffix : &str) -> String {
+        format!("{}/{}.{}.bincode", self.base_url, lang.code(), suffix)
+    }
+
+    fn path_for(&self, lang : Language, suffix : &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.{}.bincode", lang.code(), suffix))
+    }
+
+    /// Return the cached path to the dictionary for `lang`, downloading
+    /// it first if it is not already cached.
+    pub fn fetch(&self, lang : Language, suffix : &str) -> Result<PathBuf> {
+        let path = self.path_for(lang, suffix);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        let url = self.url_for(lang, suffix);
+        let bytes = download(&url)?;
+
+        // Write to a sibling temp file unique to this process and call,
+        // then rename into place, so neither a partial download nor a
+        // racing concurrent fetch of the same dictionary can leave a
+        // truncated or interleaved file at `path`.
+        let tmp_path = path.with_extension(format!("bincode.{}.tmp", unique_suffix()));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(path)
+    }
+}
+
+/// A suffix unique to this process and call, so concurrent fetches never
+/// write to the same temp file.
+fn unique_suffix() -> String {
+    static COUNTER : AtomicU32 = AtomicU32::new(0);
+    format!("{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn default_cache_dir() -> PathBuf {
+    if let Ok(dir) = env::var("HYPHENATION_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::data_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join("hyphenation")
+}
+
+fn download(url : &str) -> Result<Vec<u8>> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| Error::Fetch(e.to_string()))?;
+
+    let mut bytes = Vec::new();
+    response.into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(Error::IO)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyphenation_commons::Language::EnglishUS;
+
+    fn scratch_dir(name : &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("hyphenation-fetch-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).expect("should be able to create a scratch directory");
+        dir
+    }
+
+    #[test]
+    fn url_and_path_are_keyed_on_language_and_suffix() {
+        let source = DictionarySource::default_source()
+            .with_base_url("https://example.test/dicts")
+            .with_cache_dir(scratch_dir("keys"));
+
+        let expected_name = format!("{}.standard.bincode", EnglishUS.code());
+        assert_eq!(source.url_for(EnglishUS, "standard"), format!("https://example.test/dicts/{}", expected_name));
+        assert_eq!(source.path_for(EnglishUS, "standard").file_name().unwrap(), expected_name.as_str());
+    }
+
+    #[test]
+    fn fetch_returns_the_cached_path_without_downloading() {
+        let dir = scratch_dir("cached");
+        let source = DictionarySource::default_source().with_cache_dir(dir.clone());
+        let path = source.path_for(EnglishUS, "standard");
+        fs::write(&path, b"already cached").expect("fixture should be writable");
+
+        let fetched = source.fetch(EnglishUS, "standard").expect("a cached dictionary needs no network access");
+        assert_eq!(fetched, path);
+    }
+}
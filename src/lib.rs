@@ -0,0 +1,23 @@
+//! Hyphenation, implementing Knuth-Liang's hyphenation algorithm.
+//!
+//! See [`load`] for the ways a hyphenation dictionary can be obtained, and
+//! [`view`] for a zero-copy alternative when a dictionary is memory-mapped.
+
+pub extern crate hyphenation_commons;
+
+pub use hyphenation_commons::Language;
+pub use hyphenation_commons::dictionary::{Standard, Extended};
+
+mod load;
+pub use load::{Error, Load, Result};
+
+mod loader;
+pub use loader::Loader;
+
+mod tex;
+
+#[cfg(feature = "fetch")]
+pub mod fetch;
+
+#[cfg(feature = "mmap")]
+pub mod view;
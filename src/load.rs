@@ -56,6 +56,20 @@ let english_us = Standard::from_embedded(Language::EnglishUS) ?;
 Note that embeding significantly increases the size of the compiled artifact.
 
 
+## Compiling from raw pattern files
+
+Dictionaries can also be compiled directly from a TeX or libhyphen pattern
+file, without going through the build-time pipeline, via
+[`from_tex_patterns`][Load::from_tex_patterns].
+
+
+## Fetching on demand
+
+With the `fetch` feature enabled, dictionaries can be downloaded on
+demand into a local cache instead of being bundled or embedded, via
+[`fetch`][Load::fetch].
+
+
 [`Load`]: trait.Load.html
 [`from_path`]: trait.Load.html#method.from_path
 */
@@ -96,10 +110,23 @@ pub trait Load : Sized {
     /// Deserialize the embedded dictionary.
     fn from_embedded(lang : Language) -> Result<Self>;
 
+    /// Parse and compile a dictionary from a raw TeX `\patterns{}` file
+    /// or a wrapper-less libhyphen `.dic` file, such as a community
+    /// `hyph-*.tex`, bypassing the bincode format entirely. See
+    /// [`crate::tex`] for the accepted formats.
+    fn from_tex_patterns<R>(lang : Language, reader : R) -> Result<Self>
+    where R : io::Read;
+
+    #[cfg(feature = "fetch")]
+    /// Fetch the dictionary for `lang` from the default
+    /// [`DictionarySource`](crate::fetch::DictionarySource), downloading
+    /// it into the cache directory if it is not already there.
+    fn fetch(lang : Language) -> Result<Self>;
+
 }
 
 macro_rules! impl_load {
-    ($dict:ty, $suffix:expr) => {
+    ($dict:ty, $suffix:expr, $compile:path) => {
         impl Load for $dict {
             fn from_reader<R>(lang : Language, reader : &mut R) -> Result<Self>
             where R : io::Read {
@@ -122,12 +149,25 @@ macro_rules! impl_load {
                 let dict = bin::deserialize(dict_bytes) ?;
                 Ok(dict)
             }
+
+            fn from_tex_patterns<R>(lang : Language, reader : R) -> Result<Self>
+            where R : io::Read {
+                let (patterns, exceptions, minima) = $compile(reader)?;
+                Ok(Self { language : lang, patterns, exceptions, minima })
+            }
+
+            #[cfg(feature = "fetch")]
+            fn fetch(lang : Language) -> Result<Self> {
+                let source = crate::fetch::DictionarySource::default_source();
+                let path = source.fetch(lang, $suffix)?;
+                Self::from_path(lang, path)
+            }
         }
     }
 }
 
-impl_load! { Standard, "standard" }
-impl_load! { Extended, "extended" }
+impl_load! { Standard, "standard", crate::tex::compile_standard }
+impl_load! { Extended, "extended", crate::tex::compile_extended }
 
 
 #[cfg(feature = "embed_all")]
@@ -153,7 +193,16 @@ pub enum Error {
     /// The loaded dictionary is for the wrong language.
     LanguageMismatch { expected : Language, found : Language },
     /// The embedded dictionary could not be retrieved.
-    Resource
+    Resource,
+    /// A dictionary's source data did not match the layout expected by
+    /// the reader attempting to parse it – whether a zero-copy view's
+    /// backing bytes or a TeX/libhyphen pattern file.
+    Layout(&'static str),
+    /// Neither the requested language nor any of its configured fallbacks
+    /// could be found.
+    Unavailable { requested : Language, tried : Vec<Language> },
+    /// A dictionary could not be fetched over the network.
+    Fetch(String)
 }
 
 impl error::Error for Error {
@@ -162,7 +211,10 @@ impl error::Error for Error {
             Error::Deserialization(ref e) => e.description(),
             Error::IO(ref e) => e.description(),
             Error::LanguageMismatch { .. } => "loaded a dictionary for the wrong language",
-            Error::Resource => "embedded dictionary could not be retrieved"
+            Error::Resource => "embedded dictionary could not be retrieved",
+            Error::Layout(_) => "malformed dictionary data",
+            Error::Unavailable { .. } => "no dictionary found for the requested language or its fallbacks",
+            Error::Fetch(ref reason) => reason
         }
     }
 }
@@ -180,6 +232,12 @@ a dictionary for `{}` instead.", expected, found),
                 let e = self as &dyn error::Error;
                 e.description().fmt(f)
             }
+            Error::Layout(reason) => write!(f, "malformed dictionary data: {}", reason),
+            Error::Unavailable { requested, ref tried } =>
+                write!(f, "\
+No dictionary available for `{}`; tried the following languages in order:
+{}.", requested, tried.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")),
+            Error::Fetch(ref reason) => write!(f, "could not fetch dictionary: {}", reason)
         }
     }
 }
@@ -0,0 +1,184 @@
+/*! # A caching loader with language-fallback chains
+
+Calling [`Standard::from_path`](crate::Load::from_path) directly reloads
+and redeserializes a dictionary on every call, and offers no recourse
+when the exact requested [`Language`] is missing from disk. [`Loader`]
+wraps a search directory in a small cache, keyed by language, and a
+configurable fallback chain so that a missing regional variant resolves
+to a related dictionary instead of failing outright.
+
+```norun
+use hyphenation::Language::*;
+use hyphenation::Loader;
+
+let mut loader = Loader::new("/path/to/dictionaries");
+let english_us = loader.load(EnglishUS) ?;
+// `EnglishGB` falls back to `EnglishUS` by default.
+let english_gb = loader.load(EnglishGB) ?;
+```
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use hyphenation_commons::Language;
+use hyphenation_commons::Language::*;
+
+use crate::load::{Error, Load, Result};
+use crate::Standard;
+
+
+/// A cache of loaded [`Standard`] dictionaries, keyed by [`Language`],
+/// that falls back to a related language when the one requested is not
+/// available in the search directory.
+pub struct Loader {
+    directory : PathBuf,
+    cache : HashMap<Language, Arc<Standard>>,
+    fallbacks : HashMap<Language, Language>,
+}
+
+impl Loader {
+    /// Create a loader that reads dictionaries from `directory`, seeded
+    /// with the crate's default fallback chains (e.g. `EnglishGB` to
+    /// `EnglishUS`, `PortugueseBR` to `Portuguese`).
+    pub fn new<P : Into<PathBuf>>(directory : P) -> Self {
+        Loader {
+            directory : directory.into(),
+            cache : HashMap::new(),
+            fallbacks : default_fallbacks(),
+        }
+    }
+
+    /// Register the language to fall back to when `lang` itself cannot
+    /// be found, overriding any default for `lang`.
+    pub fn with_fallback(mut self, lang : Language, fallback : Language) -> Self {
+        self.fallbacks.insert(lang, fallback);
+        self
+    }
+
+    /// Load the dictionary for `lang`, returning the cached copy if one
+    /// has already been loaded. On a cache miss, look for
+    /// `<code>.standard.bincode` in the search directory; if it is not
+    /// there, walk the fallback chain until a dictionary is found or the
+    /// chain is exhausted or cycles back on itself, in which case
+    /// return [`Error::Unavailable`]. The result is cached under both
+    /// `lang` and the language it was ultimately found under, so a
+    /// resolved fallback is as cheap to re-load as a direct hit.
+    pub fn load(&mut self, lang : Language) -> Result<Arc<Standard>> {
+        if let Some(dict) = self.cache.get(&lang) {
+            return Ok(dict.clone());
+        }
+
+        let mut current = lang;
+        let mut tried = vec![lang];
+        let mut visited : HashSet<Language> = HashSet::new();
+        visited.insert(lang);
+
+        let dict = loop {
+            if let Some(dict) = self.cache.get(&current) {
+                break dict.clone();
+            }
+
+            match self.read(current) {
+                Ok(dict) => break Arc::new(dict),
+                Err(Error::IO(ref e)) if e.kind() == io::ErrorKind::NotFound => {
+                    match self.fallbacks.get(&current) {
+                        Some(&next) if visited.insert(next) => {
+                            tried.push(next);
+                            current = next;
+                        },
+                        _ => return Err(Error::Unavailable { requested : lang, tried }),
+                    }
+                },
+                Err(e) => return Err(e),
+            }
+        };
+
+        self.cache.insert(current, dict.clone());
+        self.cache.insert(lang, dict.clone());
+        Ok(dict)
+    }
+
+    fn read(&self, lang : Language) -> Result<Standard> {
+        let path = self.directory.join(format!("{}.standard.bincode", lang.code()));
+        Standard::from_path(lang, path)
+    }
+}
+
+fn default_fallbacks() -> HashMap<Language, Language> {
+    let mut fallbacks = HashMap::new();
+    fallbacks.insert(EnglishGB, EnglishUS);
+    fallbacks.insert(PortugueseBR, Portuguese);
+    fallbacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyphenation_commons::dictionary::{Exceptions, Patterns};
+    use std::fs;
+
+    fn minimal_standard(lang : Language) -> Standard {
+        Standard {
+            language : lang,
+            patterns : Patterns::new(),
+            exceptions : Exceptions::new(),
+            minima : hyphenation_commons::dictionary::Minima { left : 2, right : 3 },
+        }
+    }
+
+    fn scratch_dir(name : &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("hyphenation-loader-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).expect("should be able to create a scratch directory");
+        dir
+    }
+
+    fn write_dict(dir : &std::path::Path, lang : Language) {
+        let bytes = bincode::serialize(&minimal_standard(lang)).expect("dictionary should serialize");
+        fs::write(dir.join(format!("{}.standard.bincode", lang.code())), bytes)
+            .expect("dictionary fixture should be writable");
+    }
+
+    #[test]
+    fn falls_back_to_the_configured_language() {
+        let dir = scratch_dir("fallback");
+        write_dict(&dir, EnglishUS);
+
+        let mut loader = Loader::new(dir);
+        let dict = loader.load(EnglishGB).expect("EnglishGB should fall back to EnglishUS");
+        assert_eq!(dict.language, EnglishUS);
+    }
+
+    #[test]
+    fn detects_a_fallback_cycle() {
+        let dir = scratch_dir("cycle");
+        let mut loader = Loader::new(dir)
+            .with_fallback(EnglishUS, EnglishGB)
+            .with_fallback(EnglishGB, EnglishUS);
+
+        match loader.load(EnglishUS) {
+            Err(Error::Unavailable { requested, tried }) => {
+                assert_eq!(requested, EnglishUS);
+                assert_eq!(tried, vec![EnglishUS, EnglishGB]);
+            },
+            _other => panic!("expected Error::Unavailable, got something else"),
+        }
+    }
+
+    #[test]
+    fn reports_unavailable_when_the_chain_is_exhausted() {
+        let dir = scratch_dir("unavailable");
+        let mut loader = Loader::new(dir);
+
+        match loader.load(Portuguese) {
+            Err(Error::Unavailable { requested, tried }) => {
+                assert_eq!(requested, Portuguese);
+                assert_eq!(tried, vec![Portuguese]);
+            },
+            _other => panic!("expected Error::Unavailable, got something else"),
+        }
+    }
+}
@@ -0,0 +1,325 @@
+/*! # Compiling dictionaries from raw TeX/libhyphen pattern files
+
+Pre-built bincode dictionaries cover the languages this crate bundles, but
+the wider TeX and libhyphen ecosystems distribute community-maintained
+pattern files (`hyph-*.tex`, `hyph_*.dic`) for languages and dialects this
+crate does not ship. [`Load::from_tex_patterns`] parses the classic Liang
+pattern format directly, without going through the build-time pipeline,
+and compiles it into a dictionary in memory.
+
+```norun
+use std::io::BufReader;
+use std::fs::File;
+use hyphenation::{Load, Standard, Language};
+
+let file = File::open("hyph-en-us.tex") ?;
+let english_us = Standard::from_tex_patterns(Language::EnglishUS, BufReader::new(file)) ?;
+```
+
+Two source shapes are accepted:
+
+- TeX's own `\patterns{ ... }` block of whitespace-separated entries,
+  optionally paired with a `\hyphenation{ ... }` block of exception
+  words, and `\lefthyphenmin=n`/`\righthyphenmin=n` assignments;
+- libhyphen's `.dic` format, which has no `\patterns{}` wrapper: a
+  charset name on the first line, followed by one bare pattern per
+  remaining line.
+
+Each pattern entry is an alternating string of letters and single
+digits: a digit before or after a letter encodes the break priority –
+odd allows a break, even forbids it, and higher always wins – at that
+inter-letter gap, with an omitted digit defaulting to 0. A leading or
+trailing `.` anchors the entry to the start or end of the word; matching
+such a pattern therefore requires the word itself to be searched as if
+padded with the same boundary markers, which is exactly what
+[`StandardView::gaps`](crate::view::StandardView::gaps) does.
+
+[`Standard`](crate::Standard) and [`Extended`](crate::Extended)
+dictionaries are compiled by separate entry points,
+[`compile_standard`] and [`compile_extended`], because the `Extended`
+format additionally allows a pattern to carry a `/old=new,left,right`
+non-standard-hyphenation clause (as used by, e.g., German's old
+orthography to turn `"ck"` into `"k-k"` at a break) that has no
+counterpart in the standard format.
+*/
+
+use std::io;
+
+use hyphenation_commons::dictionary::{
+    Exceptions, ExtendedExceptions, ExtendedPatterns, Minima, Patterns, Substitution,
+};
+
+use crate::load::{Error, Result};
+
+
+/// TeX's own defaults when neither `\lefthyphenmin` nor
+/// `\righthyphenmin` is given.
+const DEFAULT_MINIMA : Minima = Minima { left : 2, right : 3 };
+
+/// The raw, whitespace-split contents of a pattern file, with comments
+/// stripped, before they are folded into a format-specific dictionary.
+struct Sections {
+    pattern_tokens : Vec<String>,
+    exception_tokens : Vec<String>,
+    minima : Minima,
+}
+
+fn extract_sections<R>(mut reader : R) -> Result<Sections>
+where R : io::Read {
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+
+    let uncommented : String = source.lines()
+        .map(strip_trailing_comment)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let minima = Minima {
+        left : extract_assignment(&uncommented, "\\lefthyphenmin")
+            .unwrap_or(DEFAULT_MINIMA.left),
+        right : extract_assignment(&uncommented, "\\righthyphenmin")
+            .unwrap_or(DEFAULT_MINIMA.right),
+    };
+
+    let pattern_tokens : Vec<String> = if uncommented.contains("\\patterns{") {
+        let block = extract_block(&uncommented, "\\patterns{")
+            .ok_or(Error::Layout("unterminated \\patterns{} block"))?;
+        tokenize(block)
+    } else {
+        // No `\patterns{}` tag at all: this is a bare libhyphen `.dic`
+        // file, whose first line names a charset rather than a pattern.
+        tokenize(strip_first_line(&uncommented))
+    };
+
+    if pattern_tokens.is_empty() {
+        return Err(Error::Layout("no patterns found in TeX/libhyphen source"));
+    }
+
+    let exception_tokens = extract_block(&uncommented, "\\hyphenation{")
+        .map(tokenize)
+        .unwrap_or_default();
+
+    Ok(Sections { pattern_tokens, exception_tokens, minima })
+}
+
+fn tokenize(block : &str) -> Vec<String> {
+    block.split_whitespace().map(|s| s.to_lowercase()).collect()
+}
+
+/// Parse a `\patterns{ ... }` source into a [`Standard`](crate::Standard)
+/// dictionary's pattern trie, exception table and hyphenation minima.
+pub(crate) fn compile_standard<R>(reader : R) -> Result<(Patterns, Exceptions, Minima)>
+where R : io::Read {
+    let sections = extract_sections(reader)?;
+
+    let mut patterns = Patterns::new();
+    for token in &sections.pattern_tokens {
+        let (letters, priorities) = parse_pattern(token);
+        patterns.insert(&letters, priorities);
+    }
+
+    let mut exceptions = Exceptions::new();
+    for token in &sections.exception_tokens {
+        let (word, breaks) = parse_exception(token);
+        exceptions.insert(word, breaks);
+    }
+
+    Ok((patterns, exceptions, sections.minima))
+}
+
+/// Parse a `\patterns{ ... }` source into an
+/// [`Extended`](crate::Extended) dictionary's pattern trie, exception
+/// table and hyphenation minima, additionally honoring each pattern's
+/// optional `/old=new,left,right` non-standard-hyphenation clause.
+pub(crate) fn compile_extended<R>(reader : R) -> Result<(ExtendedPatterns, ExtendedExceptions, Minima)>
+where R : io::Read {
+    let sections = extract_sections(reader)?;
+
+    let mut patterns = ExtendedPatterns::new();
+    for token in &sections.pattern_tokens {
+        let (core, substitution) = match token.split_once('/') {
+            Some((core, spec)) => (core, Some(parse_substitution(spec))),
+            None => (token.as_str(), None),
+        };
+        let (letters, priorities) = parse_pattern(core);
+        patterns.insert(&letters, priorities, substitution);
+    }
+
+    let mut exceptions = ExtendedExceptions::new();
+    for token in &sections.exception_tokens {
+        let (word, breaks) = parse_exception(token);
+        exceptions.insert(word, breaks);
+    }
+
+    Ok((patterns, exceptions, sections.minima))
+}
+
+/// Parse a `\patterns{}`/libhyphen source into its bare letters-and-
+/// priorities entries and hyphenation minima, without building a
+/// [`Patterns`] trie. It runs the same parsing as [`compile_standard`]
+/// and feeds [`crate::view`]'s zero-copy trie builder, so
+/// the zero-copy layout can be produced from the same pattern source a
+/// [`Standard`](crate::Standard) dictionary is compiled from, rather than
+/// only from a hand-built [`TrieNode`](crate::view::TrieNode) vector.
+#[cfg(feature = "mmap")]
+pub(crate) fn compile_pattern_entries<R>(reader : R) -> Result<(Vec<(String, Vec<u8>)>, Minima)>
+where R : io::Read {
+    let sections = extract_sections(reader)?;
+    let entries = sections.pattern_tokens.iter().map(|token| parse_pattern(token)).collect();
+    Ok((entries, sections.minima))
+}
+
+/// Strip everything from the first `%` on a line (TeX treats `%` as a
+/// comment leader anywhere on a line, not only at its start) to the end
+/// of the line.
+fn strip_trailing_comment(line : &str) -> &str {
+    match line.find('%') {
+        Some(idx) => &line[.. idx],
+        None => line,
+    }
+}
+
+/// Drop the first line of `source` outright – the charset declaration
+/// that always heads a wrapper-less libhyphen `.dic` file.
+fn strip_first_line(source : &str) -> &str {
+    match source.find('\n') {
+        Some(idx) => &source[idx + 1 ..],
+        None => "",
+    }
+}
+
+/// Find `tag` in `source` and return the (non-nested) text up to the
+/// next closing brace.
+fn extract_block<'a>(source : &'a str, tag : &str) -> Option<&'a str> {
+    let start = source.find(tag)? + tag.len();
+    let rest = &source[start ..];
+    let end = rest.find('}')?;
+    Some(&rest[.. end])
+}
+
+/// Find `command` – a TeX integer parameter such as `"\lefthyphenmin"` –
+/// and parse the value it is set to. TeX accepts all of `\command=2`,
+/// `\command 2` and `\command2` as an assignment, so any `=` or
+/// whitespace between the command and its digits is skipped rather than
+/// required.
+fn extract_assignment(source : &str, command : &str) -> Option<u8> {
+    let start = source.find(command)? + command.len();
+    let rest = source[start ..].trim_start_matches(|c : char| c == '=' || c.is_whitespace());
+    let digits : String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Split a single Liang pattern entry – e.g. `".hy5ph1en"` – into its
+/// letters (including any anchoring `.`) and the break priority
+/// accumulated at every gap around them, defaulting missing digits to 0.
+fn parse_pattern(token : &str) -> (String, Vec<u8>) {
+    let mut letters = String::new();
+    let mut priorities = vec![0u8];
+
+    for ch in token.chars() {
+        match ch.to_digit(10) {
+            Some(digit) => { *priorities.last_mut().unwrap() = digit as u8; },
+            None => { letters.push(ch); priorities.push(0); },
+        }
+    }
+
+    (letters, priorities)
+}
+
+/// Parse a `\hyphenation{}` exception entry – e.g. `"as-so-ciate"` –
+/// into its de-dashed word and the gap indices, counted in letters from
+/// the start of that word, where a break is permitted.
+fn parse_exception(token : &str) -> (String, Vec<usize>) {
+    let mut word = String::new();
+    let mut breaks = Vec::new();
+    let mut letter_count = 0usize;
+
+    for ch in token.chars() {
+        if ch == '-' {
+            breaks.push(letter_count);
+        } else {
+            word.push(ch);
+            letter_count += 1;
+        }
+    }
+
+    (word, breaks)
+}
+
+/// Parse the `old=new,left,right` clause of an extended pattern: the
+/// text before `=` is the matched original and is discarded (the matched
+/// letters are already known from the pattern itself), while `new` is the
+/// replacement and `left`/`right` count how many letters of the original
+/// match it covers on either side of the break.
+fn parse_substitution(spec : &str) -> Substitution {
+    let (_old, rest) = spec.split_once('=').unwrap_or(("", spec));
+    let mut fields = rest.splitn(3, ',');
+    let replacement = fields.next().unwrap_or("").to_string();
+    let left = fields.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+    let right = fields.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+
+    Substitution { replacement, left, right }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_substitution_clause() {
+        let sub = parse_substitution("ck=k-k,1,1");
+        assert_eq!(sub.replacement, "k-k");
+        assert_eq!(sub.left, 1);
+        assert_eq!(sub.right, 1);
+    }
+
+    #[test]
+    fn parse_substitution_defaults_missing_counts_to_zero() {
+        let sub = parse_substitution("ck=k-k");
+        assert_eq!(sub.replacement, "k-k");
+        assert_eq!(sub.left, 0);
+        assert_eq!(sub.right, 0);
+    }
+
+    #[test]
+    fn extract_assignment_accepts_the_equals_form() {
+        assert_eq!(extract_assignment("\\lefthyphenmin=3", "\\lefthyphenmin"), Some(3));
+    }
+
+    #[test]
+    fn extract_assignment_accepts_tex_bare_and_spaced_forms() {
+        assert_eq!(extract_assignment("\\lefthyphenmin 3", "\\lefthyphenmin"), Some(3));
+        assert_eq!(extract_assignment("\\lefthyphenmin3", "\\lefthyphenmin"), Some(3));
+    }
+
+    #[test]
+    fn parse_pattern_keeps_anchors_as_literal_dots() {
+        let (letters, priorities) = parse_pattern(".ab1c");
+        assert_eq!(letters, ".abc");
+        assert_eq!(priorities, vec![0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn extracts_patterns_exceptions_and_minima_from_a_tex_fixture() {
+        let source = "\
+\\lefthyphenmin=2
+\\righthyphenmin=3
+\\patterns{
+.ab1c hy3ph
+}
+\\hyphenation{as-so-ciate}
+";
+        let sections = extract_sections(std::io::Cursor::new(source.as_bytes())).unwrap();
+        assert_eq!(sections.pattern_tokens, vec![".ab1c", "hy3ph"]);
+        assert_eq!(sections.exception_tokens, vec!["as-so-ciate"]);
+        assert_eq!(sections.minima, Minima { left : 2, right : 3 });
+    }
+
+    #[test]
+    fn compile_standard_succeeds_on_a_minimal_fixture() {
+        let source = "\\patterns{ .ab1c hy3ph }\n\\hyphenation{as-so-ciate}\n";
+        let (_patterns, _exceptions, minima) =
+            compile_standard(std::io::Cursor::new(source.as_bytes())).unwrap();
+        assert_eq!(minima, DEFAULT_MINIMA);
+    }
+}
@@ -0,0 +1,410 @@
+/*! # Zero-copy dictionary views
+
+[`Standard::from_reader`](crate::Load::from_reader) deserializes a whole
+dictionary onto the heap before a single word can be hyphenated. That is
+wasteful for programs that keep many languages resident at once, or that
+would rather page a dictionary in on demand. This module offers a borrowed
+alternative: [`StandardView`] reads its pattern trie directly out of a
+byte slice, without copying, so the slice can be backed by a memory map.
+The zero-copy layout holds the pattern trie only; it has no exception
+table, so exception words are not looked up through a [`StandardView`].
+
+```norun
+use hyphenation::view::MappedStandard;
+use hyphenation::Language;
+
+let mapped = MappedStandard::from_mmap(Language::EnglishUS, "/path/to/english-us.hyz") ?;
+let english_us = mapped.view();
+```
+
+The zero-copy layout is produced by [`to_zerocopy_bytes`], which lays the
+trie out as flat, offset-addressed arrays rather than bincode's owned
+`Vec`s. A [`StandardView`] never copies the trie onto the heap; a lookup
+walks the mapped bytes the same way a compiled state machine walks its
+image, allocating only the buffers for the word being looked up and its
+result.
+
+[`compile_zerocopy`] builds that layout directly from a TeX/libhyphen
+pattern source, via [`build_trie`] and the same token parsing
+[`Load::from_tex_patterns`](crate::Load::from_tex_patterns) uses to build
+an owned dictionary, so the format has a real producer besides a
+hand-built [`TrieNode`] vector.
+
+Every offset read out of the backing bytes is bounds-checked, so a
+truncated or adversarial file is rejected with [`Error::Layout`] at
+`from_bytes`/`from_mmap` time – or, for a node record that turns out to
+reference out-of-range data, simply treated as a dead end during lookup –
+rather than panicking.
+
+This is gated behind the `mmap` feature.
+*/
+
+use std::convert::TryInto;
+#[cfg(feature = "mmap")] use std::fs::File;
+#[cfg(feature = "mmap")] use std::path::Path;
+
+use hyphenation_commons::Language;
+
+use crate::load::{Error, Result};
+
+
+const MAGIC : [u8; 4] = *b"HYZC";
+const VERSION : u8 = 1;
+const HEADER_LEN : usize = 6;
+const LENGTHS_LEN : usize = 12;
+
+/// A single trie node, stored as a contiguous, offset-addressed record.
+///
+/// `edges` indexes into the view's edge table: `edges_offset` is the index
+/// of the node's first outgoing edge, and `edges_len` the number of edges,
+/// sorted by character so that lookup can binary-search them. `priorities`
+/// similarly indexes into the priority table, one entry per inter-letter
+/// gap accumulated at this node.
+#[derive(Clone, Copy)]
+struct NodeRecord {
+    edges_offset : u32,
+    edges_len : u32,
+    priorities_offset : u32,
+    priorities_len : u32,
+}
+
+const NODE_SIZE : usize = 16;
+const EDGE_SIZE : usize = 8;
+
+/// Read a little-endian `u32` at `at`, or `None` if it would run past the
+/// end of `bytes`. Every offset in this module is treated as untrusted –
+/// it may come straight off a memory-mapped file – so none of these reads
+/// are allowed to panic.
+fn read_u32(bytes : &[u8], at : usize) -> Option<u32> {
+    let end = at.checked_add(4)?;
+    let slice = bytes.get(at .. end)?;
+    Some(u32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn read_node(bytes : &[u8], index : u32) -> Option<NodeRecord> {
+    let at = (index as usize).checked_mul(NODE_SIZE)?;
+    Some(NodeRecord {
+        edges_offset : read_u32(bytes, at)?,
+        edges_len : read_u32(bytes, at + 4)?,
+        priorities_offset : read_u32(bytes, at + 8)?,
+        priorities_len : read_u32(bytes, at + 12)?,
+    })
+}
+
+/// Look up the outgoing edge for `ch` from the node whose edges start at
+/// `offset` and span `len` entries, returning the index of the child node.
+fn find_edge(edges : &[u8], offset : u32, len : u32, ch : char) -> Option<u32> {
+    let target = ch as u32;
+    let mut lo = 0u32;
+    let mut hi = len;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let at = (offset.checked_add(mid)? as usize).checked_mul(EDGE_SIZE)?;
+        let edge_ch = read_u32(edges, at)?;
+        match edge_ch.cmp(&target) {
+            std::cmp::Ordering::Less => lo = mid + 1,
+            std::cmp::Ordering::Greater => hi = mid,
+            std::cmp::Ordering::Equal => return read_u32(edges, at + 4),
+        }
+    }
+    None
+}
+
+/// A borrowed dictionary, laid out for direct, zero-copy traversal over a
+/// byte slice – typically one backed by a memory-mapped file.
+///
+/// Unlike [`Standard`](hyphenation_commons::dictionary::Standard), a
+/// [`StandardView`] does not own its trie; it reads it out of `bytes` on
+/// every lookup. It holds no exception table.
+pub struct StandardView<'a> {
+    language : Language,
+    nodes : &'a [u8],
+    edges : &'a [u8],
+    priorities : &'a [u8],
+}
+
+impl<'a> StandardView<'a> {
+    /// View a dictionary serialized in the zero-copy layout, verifying
+    /// that it belongs to the requested language. Every section offset
+    /// is range-checked against `bytes` up front, so a truncated or
+    /// malformed file is rejected here rather than panicking on first
+    /// lookup.
+    pub fn from_bytes(lang : Language, bytes : &'a [u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || bytes[0 .. 4] != MAGIC {
+            return Err(Error::Layout("missing zero-copy dictionary header"));
+        }
+        if bytes[4] != VERSION {
+            return Err(Error::Layout("unsupported zero-copy dictionary version"));
+        }
+
+        let code_len = bytes[5] as usize;
+        let code_at = HEADER_LEN;
+        let code_end = code_at.checked_add(code_len)
+            .ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let code_bytes = bytes.get(code_at .. code_end)
+            .ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let code = std::str::from_utf8(code_bytes)
+            .map_err(|_| Error::Layout("invalid language code"))?;
+        let found = Language::from_code(code)
+            .ok_or(Error::Layout("unrecognized language code"))?;
+        if found != lang {
+            return Err(Error::LanguageMismatch { expected : lang, found });
+        }
+
+        let lengths_at = code_end;
+        let lengths_end = lengths_at.checked_add(LENGTHS_LEN)
+            .ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let lengths = bytes.get(lengths_at .. lengths_end)
+            .ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let nodes_len = read_u32(lengths, 0).ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let edges_len = read_u32(lengths, 4).ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+        let priorities_len = read_u32(lengths, 8).ok_or(Error::Layout("truncated zero-copy dictionary"))?;
+
+        let nodes_start = lengths_end;
+        let nodes_size = (nodes_len as usize).checked_mul(NODE_SIZE)
+            .ok_or(Error::Layout("zero-copy dictionary node table overflows"))?;
+        let nodes_end = nodes_start.checked_add(nodes_size)
+            .ok_or(Error::Layout("zero-copy dictionary node table overflows"))?;
+
+        let edges_start = nodes_end;
+        let edges_size = (edges_len as usize).checked_mul(EDGE_SIZE)
+            .ok_or(Error::Layout("zero-copy dictionary edge table overflows"))?;
+        let edges_end = edges_start.checked_add(edges_size)
+            .ok_or(Error::Layout("zero-copy dictionary edge table overflows"))?;
+
+        let priorities_start = edges_end;
+        let priorities_end = priorities_start.checked_add(priorities_len as usize)
+            .ok_or(Error::Layout("zero-copy dictionary priority table overflows"))?;
+
+        if bytes.len() < priorities_end {
+            return Err(Error::Layout("truncated zero-copy dictionary"));
+        }
+
+        Ok(StandardView {
+            language : found,
+            nodes : &bytes[nodes_start .. nodes_end],
+            edges : &bytes[edges_start .. edges_end],
+            priorities : &bytes[priorities_start .. priorities_end],
+        })
+    }
+
+    /// The language this dictionary hyphenates.
+    pub fn language(&self) -> Language { self.language }
+
+    /// The maximum break priority accumulated at every gap of `word`,
+    /// from the one before its first letter to the one after its last
+    /// (one more entry than `word` has letters), walking the trie
+    /// directly over the mapped bytes. A
+    /// node record that turns out to reference data outside its table –
+    /// which `from_bytes` cannot rule out on its own, since offsets are
+    /// nested arbitrarily deep in the trie – is treated as a dead end
+    /// rather than a panic.
+    ///
+    /// Patterns anchored with a leading or trailing `.` (see
+    /// [`crate::tex`]) only match at a word boundary, so `word` is
+    /// matched as if padded with those same boundary markers; the
+    /// padding's own gaps are trimmed back off before returning.
+    pub fn gaps(&self, word : &str) -> Vec<u8> {
+        let mut letters : Vec<char> = Vec::with_capacity(word.chars().count() + 2);
+        letters.push('.');
+        letters.extend(word.chars());
+        letters.push('.');
+
+        let mut padded_gaps = vec![0u8; letters.len() + 1];
+
+        for start in 0 .. letters.len() {
+            let mut node = 0u32;
+            for (offset, &ch) in letters[start ..].iter().enumerate() {
+                let record = match read_node(self.nodes, node) { Some(r) => r, None => break };
+                let child = match find_edge(self.edges, record.edges_offset, record.edges_len, ch) {
+                    Some(child) => child,
+                    None => break,
+                };
+                node = child;
+                let record = match read_node(self.nodes, node) { Some(r) => r, None => break };
+
+                let priorities_start = record.priorities_offset as usize;
+                let priorities_end = match priorities_start.checked_add(record.priorities_len as usize) {
+                    Some(end) => end,
+                    None => break,
+                };
+                let priorities = match self.priorities.get(priorities_start .. priorities_end) {
+                    Some(p) => p,
+                    None => break,
+                };
+
+                // `record.priorities_len` is untrusted file content and
+                // may not actually fit the letters matched so far; treat
+                // that mismatch as a dead end instead of underflowing.
+                let matched = start + offset + 2;
+                if let Some(base) = matched.checked_sub(record.priorities_len as usize) {
+                    for (gap, &priority) in priorities.iter().enumerate() {
+                        let at = base + gap;
+                        if at < padded_gaps.len() && priority > padded_gaps[at] { padded_gaps[at] = priority; }
+                    }
+                }
+            }
+        }
+
+        // `padded_gaps` includes the gaps around the two boundary
+        // markers; the gaps that matter to the caller are the ones of
+        // the original word, from its start to its end.
+        padded_gaps[1 .. padded_gaps.len() - 1].to_vec()
+    }
+}
+
+/// Serialize a compiled pattern trie and its priorities into the
+/// zero-copy layout that [`StandardView::from_bytes`] reads back, laying
+/// the trie out as the flat, offset-addressed node/edge/priority arrays
+/// described in the module documentation.
+pub fn to_zerocopy_bytes(lang : Language, trie : &[TrieNode]) -> Vec<u8> {
+    let code = lang.code();
+    let mut out = Vec::new();
+    out.extend_from_slice(&MAGIC);
+    out.push(VERSION);
+    out.push(code.len() as u8);
+    out.extend_from_slice(code.as_bytes());
+
+    let mut edges = Vec::new();
+    let mut priorities = Vec::new();
+    let mut nodes = Vec::with_capacity(trie.len() * NODE_SIZE);
+
+    for node in trie {
+        let edges_offset = (edges.len() / EDGE_SIZE) as u32;
+        let mut sorted_children = node.children.clone();
+        sorted_children.sort_by_key(|&(ch, _)| ch as u32);
+        for (ch, child) in &sorted_children {
+            edges.extend_from_slice(&(*ch as u32).to_le_bytes());
+            edges.extend_from_slice(&child.to_le_bytes());
+        }
+
+        let priorities_offset = priorities.len() as u32;
+        priorities.extend_from_slice(&node.priorities);
+
+        nodes.extend_from_slice(&edges_offset.to_le_bytes());
+        nodes.extend_from_slice(&(sorted_children.len() as u32).to_le_bytes());
+        nodes.extend_from_slice(&priorities_offset.to_le_bytes());
+        nodes.extend_from_slice(&(node.priorities.len() as u32).to_le_bytes());
+    }
+
+    out.extend_from_slice(&(trie.len() as u32).to_le_bytes());
+    out.extend_from_slice(&((edges.len() / EDGE_SIZE) as u32).to_le_bytes());
+    out.extend_from_slice(&(priorities.len() as u32).to_le_bytes());
+    out.extend_from_slice(&nodes);
+    out.extend_from_slice(&edges);
+    out.extend_from_slice(&priorities);
+    out
+}
+
+/// An owned trie node, as built in memory before being flattened by
+/// [`to_zerocopy_bytes`] into the zero-copy layout.
+#[derive(Clone, Default)]
+pub struct TrieNode {
+    /// Outgoing transitions, keyed by the next letter.
+    pub children : Vec<(char, u32)>,
+    /// The break priorities accumulated at this node's gaps.
+    pub priorities : Vec<u8>,
+}
+
+/// Build a [`TrieNode`] vector out of parsed `(letters, priorities)`
+/// pattern entries – the same shape [`crate::tex::compile_standard`]
+/// folds into a [`Patterns`](hyphenation_commons::dictionary::Patterns)
+/// trie – so the zero-copy layout has a real producer from pattern
+/// source data rather than only from hand-built nodes.
+pub fn build_trie<'a, I>(entries : I) -> Vec<TrieNode>
+where I : IntoIterator<Item = (&'a str, &'a [u8])> {
+    let mut trie = vec![TrieNode::default()];
+    for (letters, priorities) in entries {
+        let mut node = 0usize;
+        for ch in letters.chars() {
+            node = match trie[node].children.iter().find(|&&(c, _)| c == ch) {
+                Some(&(_, child)) => child as usize,
+                None => {
+                    let child = trie.len() as u32;
+                    trie.push(TrieNode::default());
+                    trie[node].children.push((ch, child));
+                    child as usize
+                },
+            };
+        }
+        trie[node].priorities = priorities.to_vec();
+    }
+    trie
+}
+
+/// Compile a TeX `\patterns{}` or libhyphen source directly into the
+/// zero-copy layout, via the same pattern parsing
+/// [`Load::from_tex_patterns`](crate::Load::from_tex_patterns) uses to
+/// build an owned [`Standard`](hyphenation_commons::dictionary::Standard).
+pub fn compile_zerocopy<R>(lang : Language, reader : R) -> Result<Vec<u8>>
+where R : std::io::Read {
+    let (entries, _minima) = crate::tex::compile_pattern_entries(reader)?;
+    let trie = build_trie(entries.iter().map(|(letters, priorities)| (letters.as_str(), priorities.as_slice())));
+    Ok(to_zerocopy_bytes(lang, &trie))
+}
+
+#[cfg(feature = "mmap")]
+/// An owned memory map paired with the dictionary it exposes.
+///
+/// Because [`StandardView`] borrows from its backing bytes, the mapping
+/// itself must outlive any view into it; [`MappedStandard`] keeps the two
+/// together so callers do not have to juggle the borrow by hand.
+pub struct MappedStandard {
+    map : memmap2::Mmap,
+    language : Language,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedStandard {
+    /// Memory-map the dictionary at `path`, verifying that it belongs to
+    /// the requested language.
+    pub fn from_mmap<P>(lang : Language, path : P) -> Result<Self>
+    where P : AsRef<Path> {
+        let file = File::open(path)?;
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        // Validate eagerly so a malformed file fails at open time rather
+        // than on first lookup.
+        let language = StandardView::from_bytes(lang, &map)?.language();
+        Ok(MappedStandard { map, language })
+    }
+
+    /// Borrow a [`StandardView`] over the mapped bytes.
+    pub fn view(&self) -> StandardView<'_> {
+        StandardView::from_bytes(self.language, &self.map)
+            .expect("mapping was already validated in `from_mmap`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn zerocopy_round_trip_agrees_with_parsed_patterns() {
+        let bytes = compile_zerocopy(Language::EnglishUS, Cursor::new(&b"\\patterns{ .ab1c hy3ph }"[..]))
+            .expect("compilation should succeed");
+        let view = StandardView::from_bytes(Language::EnglishUS, &bytes)
+            .expect("round-tripped bytes should be valid");
+
+        // `.ab1c` is anchored to the start of the word, so it only fires
+        // on "abc" itself, at the gap between 'b' and 'c'.
+        assert_eq!(view.gaps("abc"), vec![0, 0, 1, 0]);
+        // `hy3ph` is unanchored and fires wherever it occurs, here at the
+        // start of "hyph", at the gap between 'y' and 'p'.
+        assert_eq!(view.gaps("hyph"), vec![0, 0, 3, 0, 0]);
+    }
+
+    #[test]
+    fn truncated_bytes_are_rejected_as_layout_errors() {
+        let bytes = compile_zerocopy(Language::EnglishUS, Cursor::new(&b"\\patterns{ hy3ph }"[..]))
+            .expect("compilation should succeed");
+        let truncated = &bytes[.. bytes.len() - 4];
+
+        match StandardView::from_bytes(Language::EnglishUS, truncated) {
+            Err(Error::Layout(_)) => {},
+            Err(other) => panic!("expected Error::Layout, got a different error: {:?}", other),
+            Ok(_) => panic!("expected Error::Layout, but truncated bytes were accepted"),
+        }
+    }
+}